@@ -0,0 +1,53 @@
+use std::fmt;
+use std::io;
+
+/// Errors produced by [`crate::Db`] operations.
+///
+/// `Display` messages are considered part of the public API: callers may
+/// match on a substring (e.g. `"corrupted record at offset"`) to assert on
+/// a specific failure mode in tests.
+#[derive(Debug)]
+pub enum RabitError {
+    /// The requested key has no record in the database.
+    NotFound,
+    /// A record's stored checksum did not match its contents. `offset` is
+    /// the byte offset of the start of the bad record in the log file.
+    Corrupted { offset: u64 },
+    /// A key exceeds the maximum length a record header can encode.
+    KeyTooLarge,
+    /// A value exceeds the maximum length a record header can encode.
+    ValueTooLarge,
+    /// The underlying log file could not be read or written.
+    Io(io::Error),
+}
+
+impl fmt::Display for RabitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RabitError::NotFound => write!(f, "key not found"),
+            RabitError::Corrupted { offset } => {
+                write!(f, "corrupted record at offset {}", offset)
+            }
+            RabitError::KeyTooLarge => write!(f, "key exceeds maximum length of u32::MAX bytes"),
+            RabitError::ValueTooLarge => {
+                write!(f, "value exceeds maximum length of u32::MAX bytes")
+            }
+            RabitError::Io(e) => write!(f, "io error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RabitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RabitError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RabitError {
+    fn from(e: io::Error) -> Self {
+        RabitError::Io(e)
+    }
+}
@@ -1,21 +1,12 @@
-pub fn greet(name: &str) {
-    println!("Hello, {}!", name);
-}
-
-// Placeholder for testing boilerplate
-
-#[cfg(test)]
-mod tests {
-    use super::*; // not correct
-
-    #[test]
-    fn test_add() {
-        assert_eq!(add(2, 3), 5);
-    }
-
-    #[test]
-    #[should_panic]
-    fn test_add_should_panic() {
-        panic!("This test will fail on purpose");
-    }
-}
+//! rabitdb: a small embedded key/value storage engine.
+//!
+//! The on-disk format is an append-only log of records. On [`Db::open`] the
+//! whole log is scanned once to rebuild an in-memory index mapping each live
+//! key to the offset of its most recent record, so lookups after startup are a
+//! single seek + read rather than a linear scan.
+
+mod db;
+mod error;
+
+pub use db::Db;
+pub use error::RabitError;